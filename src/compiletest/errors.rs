@@ -9,19 +9,63 @@
 // except according to those terms.
 use self::WhichLine::*;
 
+use std::fmt;
 use std::fs::File;
 use std::io::BufReader;
 use std::io::prelude::*;
 use std::path::Path;
+use std::str::FromStr;
 
 pub struct ExpectedError {
     pub line_num: usize,
-    pub kind: String,
+    pub kind: Option<ErrorKind>,
     pub msg: String,
 }
 
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum ErrorKind {
+    Help,
+    Error,
+    Note,
+    Suggestion,
+    Warning,
+}
+
+impl FromStr for ErrorKind {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<ErrorKind, ()> {
+        let s = s.to_lowercase();
+        match &s[..] {
+            "help" => Ok(ErrorKind::Help),
+            "error" => Ok(ErrorKind::Error),
+            "note" => Ok(ErrorKind::Note),
+            "suggestion" => Ok(ErrorKind::Suggestion),
+            "warning" => Ok(ErrorKind::Warning),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ErrorKind::Help => write!(f, "help message"),
+            ErrorKind::Error => write!(f, "error"),
+            ErrorKind::Note => write!(f, "note"),
+            ErrorKind::Suggestion => write!(f, "suggestion"),
+            ErrorKind::Warning => write!(f, "warning"),
+        }
+    }
+}
+
 #[derive(PartialEq, Debug)]
-enum WhichLine { ThisLine, FollowPrevious(usize), AdjustBackward(usize) }
+enum WhichLine {
+    ThisLine,
+    FollowPrevious(usize),
+    AdjustBackward(usize),
+    AdjustForward(usize),
+}
 
 /// Looks for either "//~| KIND MESSAGE" or "//~^^... KIND MESSAGE"
 /// The former is a "follow" that inherits its target from the preceding line;
@@ -31,6 +75,13 @@ enum WhichLine { ThisLine, FollowPrevious(usize), AdjustBackward(usize) }
 /// and also //~^ ERROR message one for the preceding line, and
 ///          //~| ERROR message two for that same line.
 ///
+/// A run of `v` characters (//~vvv ERROR ...) works the same way but in
+/// the opposite direction, pointing down at a line that follows.
+///
+/// The KIND is optional: "//~ some substring" with no recognized kind
+/// in front of it is accepted as a message-only assertion, which only
+/// checks the message text and ignores the diagnostic's kind.
+///
 /// If cfg is not None (i.e., in an incremental test), then we look
 /// for `//[X]~` instead, where `X` is the current `cfg`.
 pub fn load_errors(testfile: &Path, cfg: Option<&str>) -> Vec<ExpectedError> {
@@ -75,31 +126,50 @@ fn parse_expected(last_nonfollow_error: Option<usize>,
                   tag: &str)
                   -> Option<(WhichLine, ExpectedError)> {
     let start = match line.find(tag) { Some(i) => i, None => return None };
-    let (follow, adjusts) = if line.char_at(start + tag.len()) == '|' {
-        (true, 0)
+    let (follow, adjusts, adjusts_forward) = if line.char_at(start + tag.len()) == '|' {
+        (true, 0, 0)
     } else {
-        (false, line[start + tag.len()..].chars().take_while(|c| *c == '^').count())
+        let markers = line[start + tag.len()..]
+                          .chars()
+                          .take_while(|c| *c == '^' || *c == 'v')
+                          .collect::<String>();
+        let ups = markers.chars().filter(|&c| c == '^').count();
+        let downs = markers.chars().filter(|&c| c == 'v').count();
+        assert!(ups == 0 || downs == 0,
+                "use either //~^ or //~v, not both, in an adjustment annotation.");
+        (false, ups, downs)
     };
-    let kind_start = start + tag.len() + adjusts + (follow as usize);
+    let kind_start = start + tag.len() + adjusts + adjusts_forward + (follow as usize);
     let letters = line[kind_start..].chars();
-    let kind = letters.skip_while(|c| c.is_whitespace())
-                      .take_while(|c| !c.is_whitespace())
-                      .flat_map(|c| c.to_lowercase())
-                      .collect::<String>();
-    let letters = line[kind_start..].chars();
-    let msg = letters.skip_while(|c| c.is_whitespace())
-                     .skip_while(|c| !c.is_whitespace())
-                     .collect::<String>().trim().to_owned();
+    let kind_str = letters.skip_while(|c| c.is_whitespace())
+                          .take_while(|c| !c.is_whitespace())
+                          .collect::<String>();
+    let kind = kind_str.parse().ok();
+    // If the word right after the tag isn't a recognized `ErrorKind`, it's
+    // not a kind at all -- treat it as the start of the message instead of
+    // silently discarding it.
+    let rest = line[kind_start..].trim_left();
+    let msg = if kind.is_some() {
+        rest[kind_str.len()..].trim().to_owned()
+    } else {
+        rest.to_owned()
+    };
 
     let (which, line_num) = if follow {
-        assert!(adjusts == 0, "use either //~| or //~^, not both.");
+        assert!(adjusts == 0 && adjusts_forward == 0,
+                "use either //~| or //~^/-v, not both.");
         let line_num = last_nonfollow_error.expect("encountered //~| without \
                                                     preceding //~^ line.");
         (FollowPrevious(line_num), line_num)
     } else {
-        let which =
-            if adjusts > 0 { AdjustBackward(adjusts) } else { ThisLine };
-        let line_num = line_num - adjusts;
+        let which = if adjusts > 0 {
+            AdjustBackward(adjusts)
+        } else if adjusts_forward > 0 {
+            AdjustForward(adjusts_forward)
+        } else {
+            ThisLine
+        };
+        let line_num = line_num - adjusts + adjusts_forward;
         (which, line_num)
     };
 